@@ -0,0 +1,72 @@
+//! Divergence-From-Randomness (DFR) scoring kernels.
+//!
+//! DFR models term frequency as a divergence from a randomness model instead of BM25's
+//! saturation curve, which gives a parameter-light alternative whose length normalization
+//! behaves differently on long documents.
+
+/// PL2: DFR with Normalization 2 (length-based TF renormalization) and a Poisson randomness
+/// model.
+///
+/// The raw count is first renormalized with Normalization 2:
+///
+/// \( tf_n = tf \cdot \log_2(1 + c \cdot avg\_doc\_len / doc\_len) \)
+///
+/// Under a Poisson assumption with mean `lambda = term_collection_freq / n_docs`, the
+/// information content of observing `tf_n` occurrences is (via Stirling's approximation):
+///
+/// \( score = \frac{1}{tf_n + 1} \Big( tf_n \log_2(tf_n / \lambda) + (\lambda + \frac{1}{12 \cdot tf_n} - tf_n) \log_2(e) + 0.5 \log_2(2 \pi tf_n) \Big) \)
+///
+/// `c` is the Normalization 2 hyperparameter (conventionally in `1.0..=7.0`).
+///
+/// Robustness notes:
+/// - `tf <= 0` returns 0.0.
+/// - `doc_len`, `avg_doc_len`, `tf_n`, and `lambda` are clamped away from 0 to avoid `NaN`/`-inf`
+///   from the logs.
+pub fn dfr_pl2(tf: f32, doc_len: f32, avg_doc_len: f32, term_collection_freq: f32, n_docs: f32, c: f32) -> f32 {
+    if tf <= 0.0 {
+        return 0.0;
+    }
+    let doc_len = doc_len.max(1e-9);
+    let avg_doc_len = avg_doc_len.max(1e-9);
+    let n_docs = n_docs.max(1e-9);
+    let tf_n = (tf * (1.0 + c * avg_doc_len / doc_len).log2()).max(1e-9);
+    let lambda = (term_collection_freq / n_docs).max(1e-9);
+
+    let log2_e = std::f32::consts::LOG2_E;
+    (1.0 / (tf_n + 1.0))
+        * (tf_n * (tf_n / lambda).log2()
+            + (lambda + 1.0 / (12.0 * tf_n) - tf_n) * log2_e
+            + 0.5 * (2.0 * std::f32::consts::PI * tf_n).log2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dfr_pl2_zero_tf_is_zero() {
+        assert_eq!(dfr_pl2(0.0, 100.0, 100.0, 50.0, 1000.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn dfr_pl2_is_finite_and_non_negative_for_typical_inputs() {
+        let score = dfr_pl2(3.0, 50.0, 20.0, 200.0, 1000.0, 1.0);
+        assert!(score.is_finite());
+        assert!(score >= 0.0);
+    }
+
+    #[test]
+    fn dfr_pl2_guards_against_zero_doc_len() {
+        let score = dfr_pl2(3.0, 0.0, 20.0, 200.0, 1000.0, 1.0);
+        assert!(score.is_finite());
+    }
+
+    #[test]
+    fn dfr_pl2_rare_terms_score_higher_than_common_terms() {
+        // A term that is rare in the collection should carry more information than a common one
+        // at the same in-document tf.
+        let rare = dfr_pl2(3.0, 50.0, 20.0, 5.0, 1000.0, 1.0);
+        let common = dfr_pl2(3.0, 50.0, 20.0, 900.0, 1000.0, 1.0);
+        assert!(rare > common);
+    }
+}
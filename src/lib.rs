@@ -8,6 +8,8 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod dfr;
+
 /// Okapi/BM25-style IDF with a +1 inside the log to keep values non-negative.
 ///
 /// \( \mathrm{idf} = \ln( ( (N - df + 0.5) / (df + 0.5) ) + 1 ) \)
@@ -40,6 +42,109 @@ pub fn bm25_tf(tf: f32, doc_len: f32, avg_doc_len: f32, k1: f32, b: f32) -> f32
     (tf * (k1 + 1.0)) / denom.max(1e-9)
 }
 
+/// Lower-bounded TF normalization variants that address BM25's over-penalization of long
+/// documents that genuinely contain the term.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TfNorm {
+    /// The ordinary BM25 TF normalization (no lower bound).
+    Bm25,
+    /// BM25+: adds a constant `delta` to the TF component so any occurrence contributes at
+    /// least `delta * idf`.
+    Bm25Plus {
+        /// Lower-bound constant, conventionally `1.0`.
+        delta: f32,
+    },
+    /// BM25L: shifts the length-normalized count by `delta` before saturation.
+    Bm25L {
+        /// Shift constant, conventionally `~0.5`.
+        delta: f32,
+    },
+}
+
+/// BM25 term-frequency normalization with a selectable lower-bounding variant.
+///
+/// [`TfNorm::Bm25`] reproduces [`bm25_tf`] exactly. [`TfNorm::Bm25Plus`] computes the ordinary
+/// saturated TF and adds `delta`. [`TfNorm::Bm25L`] shifts the length-normalized count
+/// `c = tf / (1 - b + b * doc_len / avg_doc_len)` by `delta` before saturating:
+/// `(k1 + 1) * (c + delta) / (k1 + c + delta)`.
+///
+/// Robustness notes:
+/// - `tf <= 0` returns 0.0 for all variants (the `delta` bump only applies when the term is
+///   present).
+/// - `avg_doc_len` and all denominators are clamped away from 0.
+pub fn bm25_tf_variant(tf: f32, doc_len: f32, avg_doc_len: f32, k1: f32, b: f32, norm: TfNorm) -> f32 {
+    if tf <= 0.0 {
+        return 0.0;
+    }
+    let avg = avg_doc_len.max(1e-9);
+    let k1 = k1.max(0.0);
+    let b = b.clamp(0.0, 1.0);
+    match norm {
+        TfNorm::Bm25 => bm25_tf(tf, doc_len, avg_doc_len, k1, b),
+        TfNorm::Bm25Plus { delta } => bm25_tf(tf, doc_len, avg_doc_len, k1, b) + delta,
+        TfNorm::Bm25L { delta } => {
+            let c = tf / (1.0 - b + b * (doc_len / avg)).max(1e-9);
+            let shifted = c + delta;
+            (k1 + 1.0) * shifted / (k1 + shifted).max(1e-9)
+        }
+    }
+}
+
+/// A single field's term-frequency statistics for [`bm25f_tf`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    /// Term frequency within this field.
+    pub tf: f32,
+    /// Length of this field (in the same units as `avg_doc_len`).
+    pub doc_len: f32,
+    /// Average length of this field across the corpus.
+    pub avg_doc_len: f32,
+    /// Field weight (boost), e.g. title > body.
+    pub weight: f32,
+    /// Per-field length-normalization knob, in `[0, 1]`.
+    pub b: f32,
+}
+
+/// BM25F score over multiple weighted fields.
+///
+/// Per BM25F, each field's count is length-normalized and weighted, the results are summed into
+/// a single pseudo-frequency, saturation is applied once against `k1`, and the result is scaled
+/// by [`bm25_idf_plus1`]:
+///
+/// \( \tilde{tf} = \sum_f w_f \cdot \frac{tf_f}{1 - b_f + b_f \cdot doc\_len_f / avg\_doc\_len_f} \)
+///
+/// \( \mathrm{score} = \frac{\tilde{tf} \cdot (k1 + 1)}{\tilde{tf} + k1} \cdot \mathrm{bm25\_idf\_plus1}(n\_docs, df) \)
+///
+/// Saturating after summing (rather than per field) is the key difference from scoring each
+/// field with [`bm25_tf`] and adding the results: it lets term frequency spread across zones
+/// saturate jointly instead of each zone earning its own diminishing-returns credit, which
+/// over-counts a term spread across several zones.
+///
+/// Robustness notes:
+/// - Fields with `tf <= 0` contribute 0.
+/// - Each field's `avg_doc_len` and the per-field denominator are clamped away from 0.
+/// - Returns 0.0 if every field contributes 0 (mirrors `bm25_tf`'s `tf <= 0 => 0` behavior).
+pub fn bm25f_tf(fields: &[Field], k1: f32, n_docs: u32, df: u32) -> f32 {
+    let k1 = k1.max(0.0);
+    let tf_tilde: f32 = fields
+        .iter()
+        .map(|f| {
+            if f.tf <= 0.0 {
+                return 0.0;
+            }
+            let avg = f.avg_doc_len.max(1e-9);
+            let b = f.b.clamp(0.0, 1.0);
+            let denom = (1.0 - b + b * (f.doc_len / avg)).max(1e-9);
+            f.weight * (f.tf / denom)
+        })
+        .sum();
+    if tf_tilde <= 0.0 {
+        return 0.0;
+    }
+    let saturated = (tf_tilde * (k1 + 1.0)) / (tf_tilde + k1).max(1e-9);
+    saturated * bm25_idf_plus1(n_docs, df)
+}
+
 /// TF transform variants.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TfVariant {
@@ -98,6 +203,28 @@ pub enum SmoothingMethod {
         /// Prior strength.
         mu: f32,
     },
+    /// Two-stage smoothing: Dirichlet first, then Jelinek–Mercer interpolation with the corpus.
+    TwoStage {
+        /// Outer interpolation weight given to the corpus probability (`1.0` returns `P(t|C)`).
+        lambda: f32,
+        /// Inner Dirichlet prior strength.
+        mu: f32,
+    },
+    /// Absolute discounting: subtract a fixed `delta` from each seen count and redistribute the
+    /// escaped mass proportional to the number of unique terms in the document.
+    AbsoluteDiscounting {
+        /// Discount subtracted from each seen count, in `[0, 1]`.
+        delta: f32,
+        /// Number of unique terms in the document.
+        unique_terms: f32,
+    },
+    /// Additive (Laplace) smoothing.
+    Additive {
+        /// Additive constant (`1.0` recovers classic Laplace smoothing).
+        alpha: f32,
+        /// Vocabulary size.
+        vocab_size: f32,
+    },
 }
 
 impl Default for SmoothingMethod {
@@ -111,6 +238,8 @@ impl Default for SmoothingMethod {
 /// - `tf`: term frequency in doc
 /// - `doc_len`: document length
 /// - `p_corpus`: corpus probability \(P(t|C)\)
+///
+/// The result is clamped to `[0, 1]` regardless of `smoothing`.
 pub fn lm_smoothed_p(tf: f32, doc_len: f32, p_corpus: f32, smoothing: SmoothingMethod) -> f32 {
     let p_corpus = p_corpus.clamp(0.0, 1.0);
     match smoothing {
@@ -128,6 +257,163 @@ pub fn lm_smoothed_p(tf: f32, doc_len: f32, p_corpus: f32, smoothing: SmoothingM
                 0.0
             }
         }
+        SmoothingMethod::TwoStage { lambda, mu } => {
+            let lam = lambda.clamp(0.0, 1.0);
+            let mu = mu.max(0.0);
+            let denom = doc_len + mu;
+            let p_dirichlet = if denom > 0.0 {
+                (tf + mu * p_corpus) / denom
+            } else {
+                0.0
+            };
+            (1.0 - lam) * p_dirichlet + lam * p_corpus
+        }
+        SmoothingMethod::AbsoluteDiscounting { delta, unique_terms } => {
+            if doc_len <= 0.0 {
+                return 0.0;
+            }
+            let delta = delta.clamp(0.0, 1.0);
+            let unique_terms = unique_terms.max(0.0);
+            let discounted = (tf - delta).max(0.0) / doc_len;
+            let redistributed = (delta * unique_terms / doc_len) * p_corpus;
+            discounted + redistributed
+        }
+        SmoothingMethod::Additive { alpha, vocab_size } => {
+            let alpha = alpha.max(0.0);
+            let vocab_size = vocab_size.max(0.0);
+            let denom = doc_len + alpha * vocab_size;
+            if denom > 0.0 {
+                (tf + alpha) / denom
+            } else {
+                0.0
+            }
+        }
+    }
+    .clamp(0.0, 1.0)
+}
+
+/// Zhai–Lafferty leave-one-out log-likelihood for a single document at a candidate Dirichlet
+/// `mu`, summed over its terms.
+///
+/// For each term, one occurrence is held out and scored against the model trained on the rest of
+/// the document: `tf_t * ln( (tf_t - 1 + mu * P(t|C)) / (doc_len - 1 + mu) )`. Terms whose log
+/// argument would be non-positive (e.g. a hapax with `tf_t == 1` and `mu * p_corpus == 0`) are
+/// skipped rather than contributing `-inf`.
+///
+/// Returns 0.0 if `doc_len <= 1.0` (there is nothing left to hold out against).
+fn dirichlet_loo_log_likelihood(doc_term_freqs: &[(f32, f32)], doc_len: f32, mu: f32) -> f32 {
+    if doc_len <= 1.0 {
+        return 0.0;
+    }
+    let mu = mu.max(0.0);
+    let denom = doc_len - 1.0 + mu;
+    if denom <= 0.0 {
+        return 0.0;
+    }
+    doc_term_freqs
+        .iter()
+        .map(|&(tf, p_corpus)| {
+            let p_corpus = p_corpus.clamp(0.0, 1.0);
+            let numer = tf - 1.0 + mu * p_corpus;
+            if numer <= 0.0 {
+                0.0
+            } else {
+                tf * (numer / denom).ln()
+            }
+        })
+        .sum()
+}
+
+/// Select the Dirichlet `mu` (from `candidates`) maximizing the Zhai–Lafferty leave-one-out
+/// log-likelihood for a single document, rather than hard-coding `mu` (e.g. the conventional
+/// `1000.0`).
+///
+/// `doc_term_freqs` is `(tf, p_corpus)` for each term observed in the document. Returns the best
+/// candidate, or the first candidate if `doc_len <= 1.0` (not enough data to hold a term out),
+/// or `0.0` if `candidates` is empty.
+pub fn optimal_dirichlet_mu(doc_term_freqs: &[(f32, f32)], doc_len: f32, candidates: &[f32]) -> f32 {
+    optimal_dirichlet_mu_over_collection(&[(doc_term_freqs, doc_len)], candidates)
+}
+
+/// Collection-level variant of [`optimal_dirichlet_mu`]: selects the `mu` maximizing the sum of
+/// per-document leave-one-out log-likelihoods over `docs`, where each document is
+/// `(doc_term_freqs, doc_len)`.
+pub fn optimal_dirichlet_mu_over_collection(docs: &[(&[(f32, f32)], f32)], candidates: &[f32]) -> f32 {
+    let Some(&first) = candidates.first() else {
+        return 0.0;
+    };
+    candidates
+        .iter()
+        .copied()
+        .map(|mu| {
+            let ll: f32 = docs
+                .iter()
+                .map(|&(term_freqs, doc_len)| dirichlet_loo_log_likelihood(term_freqs, doc_len, mu))
+                .sum();
+            (mu, ll)
+        })
+        .fold((first, f32::NEG_INFINITY), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .0
+}
+
+/// Score a batch of BM25 postings in one call, writing one score per posting into `out`.
+///
+/// This hoists the per-call invariants (`k1 * (1 - b)`, `k1 * b / avg_doc_len`, and `idf`) out of
+/// the loop, and scores `tfs[i]` against `doc_lens[i]` for every `i`. Equivalent to calling
+/// `idf * bm25_tf(tfs[i], doc_lens[i], avg_doc_len, k1, b)` per posting, but without
+/// recomputing the IDF or the per-call invariants on every iteration.
+///
+/// The inner loop has no early returns: a non-positive `tf` is masked to a 0.0 score rather than
+/// branched around, so the loop shape stays vectorizer-friendly.
+///
+/// # Panics
+///
+/// Panics if `tfs`, `doc_lens`, and `out` do not all have the same length.
+pub fn score_postings(tfs: &[f32], doc_lens: &[f32], avg_doc_len: f32, k1: f32, b: f32, idf: f32, out: &mut [f32]) {
+    assert_eq!(tfs.len(), doc_lens.len(), "tfs and doc_lens must have the same length");
+    assert_eq!(tfs.len(), out.len(), "tfs and out must have the same length");
+
+    let avg = avg_doc_len.max(1e-9);
+    let k1 = k1.max(0.0);
+    let b = b.clamp(0.0, 1.0);
+    let k1_base = k1 * (1.0 - b);
+    let k1_len_term = k1 * b / avg;
+
+    for i in 0..tfs.len() {
+        let tf = tfs[i];
+        let present = if tf > 0.0 { 1.0 } else { 0.0 };
+        let denom = (tf + k1_base + k1_len_term * doc_lens[i]).max(1e-9);
+        out[i] = present * idf * (tf * (k1 + 1.0)) / denom;
+    }
+}
+
+/// Score a batch of postings under Dirichlet-smoothed query likelihood, writing one score per
+/// posting into `out`.
+///
+/// Equivalent to calling `lm_smoothed_p(tfs[i], doc_lens[i], p_corpus[i], SmoothingMethod::Dirichlet { mu })`
+/// per posting, but hoisting `mu` out of the loop and avoiding the branch-per-call that
+/// [`lm_smoothed_p`]'s general `SmoothingMethod` dispatch requires.
+///
+/// # Panics
+///
+/// Panics if `tfs`, `doc_lens`, `p_corpus`, and `out` do not all have the same length.
+pub fn score_postings_lm(tfs: &[f32], doc_lens: &[f32], p_corpus: &[f32], mu: f32, out: &mut [f32]) {
+    assert_eq!(tfs.len(), doc_lens.len(), "tfs and doc_lens must have the same length");
+    assert_eq!(tfs.len(), p_corpus.len(), "tfs and p_corpus must have the same length");
+    assert_eq!(tfs.len(), out.len(), "tfs and out must have the same length");
+
+    let mu = mu.max(0.0);
+
+    for i in 0..tfs.len() {
+        let denom = doc_lens[i] + mu;
+        let present = if denom > 0.0 { 1.0 } else { 0.0 };
+        out[i] = (present * (tfs[i] + mu * p_corpus[i].clamp(0.0, 1.0)) / denom.max(1e-9)).clamp(0.0, 1.0);
     }
 }
 
@@ -180,6 +466,79 @@ mod tests {
         assert!(idf_transform(1000, 10, IdfVariant::Smoothed) > 0.0);
     }
 
+    #[test]
+    fn bm25_tf_variant_bm25_matches_bm25_tf() {
+        let a = bm25_tf(3.0, 50.0, 20.0, 1.2, 0.75);
+        let b = bm25_tf_variant(3.0, 50.0, 20.0, 1.2, 0.75, TfNorm::Bm25);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn bm25_tf_variant_zero_tf_is_zero_for_all_variants() {
+        for norm in [TfNorm::Bm25, TfNorm::Bm25Plus { delta: 1.0 }, TfNorm::Bm25L { delta: 0.5 }] {
+            assert_eq!(bm25_tf_variant(0.0, 100.0, 100.0, 1.2, 0.75, norm), 0.0);
+        }
+    }
+
+    #[test]
+    fn bm25_tf_variant_bm25plus_adds_delta() {
+        let base = bm25_tf(1.0, 500.0, 20.0, 1.2, 0.75);
+        let plus = bm25_tf_variant(1.0, 500.0, 20.0, 1.2, 0.75, TfNorm::Bm25Plus { delta: 1.0 });
+        assert!((plus - (base + 1.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bm25_tf_variant_bm25l_lower_bounds_long_documents() {
+        // A very long document where the term appears should not be penalized to near-zero.
+        let long_doc = bm25_tf_variant(1.0, 10_000.0, 20.0, 1.2, 0.75, TfNorm::Bm25L { delta: 0.5 });
+        let plain = bm25_tf(1.0, 10_000.0, 20.0, 1.2, 0.75);
+        assert!(long_doc > plain);
+    }
+
+    #[test]
+    fn bm25f_tf_zero_when_all_fields_absent() {
+        let fields = [
+            Field { tf: 0.0, doc_len: 5.0, avg_doc_len: 5.0, weight: 2.0, b: 0.75 },
+            Field { tf: 0.0, doc_len: 20.0, avg_doc_len: 20.0, weight: 1.0, b: 0.75 },
+        ];
+        assert_eq!(bm25f_tf(&fields, 1.2, 1000, 10), 0.0);
+    }
+
+    #[test]
+    fn bm25f_tf_saturates_jointly_not_per_field() {
+        // Saturating once after summing weighted per-field pseudo-frequencies (what bm25f_tf
+        // does) must diverge from a naive baseline that saturates each field individually with
+        // bm25_tf and then sums those: the saturation curve is concave, so per-field saturation
+        // double-dips on the "diminishing returns" benefit in a way joint saturation does not.
+        // Fields need different doc_len/avg_doc_len ratios, or per-field normalization is a
+        // no-op and both approaches coincide.
+        let k1 = 1.2;
+        let fields = [
+            Field { tf: 3.0, doc_len: 5.0, avg_doc_len: 5.0, weight: 1.0, b: 0.75 },
+            Field { tf: 3.0, doc_len: 50.0, avg_doc_len: 10.0, weight: 1.0, b: 0.75 },
+        ];
+
+        let joint = bm25f_tf(&fields, k1, 1000, 10);
+        let naive: f32 = fields
+            .iter()
+            .map(|f| f.weight * bm25_tf(f.tf, f.doc_len, f.avg_doc_len, k1, f.b))
+            .sum::<f32>()
+            * bm25_idf_plus1(1000, 10);
+
+        assert!(joint > 0.0);
+        assert!(naive > 0.0);
+        // Concavity of the saturation curve makes per-field saturate-then-sum strictly larger
+        // than saturate-after-sum.
+        assert!(joint < naive);
+    }
+
+    #[test]
+    fn bm25f_tf_weights_fields() {
+        let low_weight = [Field { tf: 2.0, doc_len: 10.0, avg_doc_len: 10.0, weight: 1.0, b: 0.75 }];
+        let high_weight = [Field { tf: 2.0, doc_len: 10.0, avg_doc_len: 10.0, weight: 5.0, b: 0.75 }];
+        assert!(bm25f_tf(&high_weight, 1.2, 1000, 10) > bm25f_tf(&low_weight, 1.2, 1000, 10));
+    }
+
     #[test]
     fn lm_smoothed_p_is_bounded_for_valid_inputs() {
         let p = lm_smoothed_p(
@@ -206,4 +565,166 @@ mod tests {
         assert!(p >= 0.0);
         assert!(p <= 1.0);
     }
+
+    #[test]
+    fn lm_smoothed_p_two_stage_interpolates_dirichlet_with_corpus() {
+        let p_corpus = 0.01;
+        let p_dirichlet_only = lm_smoothed_p(3.0, 10.0, p_corpus, SmoothingMethod::Dirichlet { mu: 1000.0 });
+        let p_two_stage = lm_smoothed_p(
+            3.0,
+            10.0,
+            p_corpus,
+            SmoothingMethod::TwoStage { lambda: 0.0, mu: 1000.0 },
+        );
+        // lambda=0 means no extra corpus interpolation beyond Dirichlet.
+        assert!((p_dirichlet_only - p_two_stage).abs() < 1e-6);
+
+        let p_full_corpus = lm_smoothed_p(
+            3.0,
+            10.0,
+            p_corpus,
+            SmoothingMethod::TwoStage { lambda: 1.0, mu: 1000.0 },
+        );
+        // lambda=1 means the result collapses entirely to the corpus probability.
+        assert!((p_full_corpus - p_corpus).abs() < 1e-6);
+    }
+
+    #[test]
+    fn lm_smoothed_p_absolute_discounting_guards_zero_doc_len() {
+        let p = lm_smoothed_p(
+            3.0,
+            0.0,
+            0.01,
+            SmoothingMethod::AbsoluteDiscounting { delta: 0.7, unique_terms: 5.0 },
+        );
+        assert_eq!(p, 0.0);
+    }
+
+    #[test]
+    fn lm_smoothed_p_absolute_discounting_is_bounded() {
+        let p = lm_smoothed_p(
+            3.0,
+            10.0,
+            0.01,
+            SmoothingMethod::AbsoluteDiscounting { delta: 0.7, unique_terms: 5.0 },
+        );
+        assert!(p >= 0.0);
+        assert!(p <= 1.0);
+    }
+
+    #[test]
+    fn lm_smoothed_p_additive_matches_laplace_formula() {
+        let p = lm_smoothed_p(3.0, 10.0, 0.01, SmoothingMethod::Additive { alpha: 1.0, vocab_size: 1000.0 });
+        let expected: f32 = (3.0 + 1.0) / (10.0 + 1.0 * 1000.0);
+        assert!((p - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn optimal_dirichlet_mu_returns_first_candidate_when_doc_too_short() {
+        let doc = [(3.0, 0.01)];
+        let mu = optimal_dirichlet_mu(&doc, 1.0, &[10.0, 1000.0, 5000.0]);
+        assert_eq!(mu, 10.0);
+    }
+
+    #[test]
+    fn optimal_dirichlet_mu_returns_zero_when_candidates_empty() {
+        let doc = [(3.0, 0.01)];
+        let mu = optimal_dirichlet_mu(&doc, 50.0, &[]);
+        assert_eq!(mu, 0.0);
+    }
+
+    #[test]
+    fn optimal_dirichlet_mu_prefers_mu_matching_the_generating_distribution() {
+        // A document whose term frequencies closely track the corpus distribution should be
+        // best explained by a large mu (heavy smoothing toward the corpus).
+        let doc: Vec<(f32, f32)> = vec![(5.0, 0.05), (5.0, 0.05), (5.0, 0.05), (5.0, 0.05)];
+        let candidates = [1.0, 10.0, 100.0, 1000.0, 5000.0];
+        let mu = optimal_dirichlet_mu(&doc, 100.0, &candidates);
+        assert!(candidates.contains(&mu));
+        assert!(mu >= 100.0);
+    }
+
+    #[test]
+    fn optimal_dirichlet_mu_prefers_small_mu_when_document_diverges_from_corpus() {
+        // A document dominated by a keyword repeated far more often than its corpus rate is
+        // best explained by trusting the document's own counts (small mu) rather than smoothing
+        // heavily toward a corpus rate the document clearly doesn't follow.
+        let doc: Vec<(f32, f32)> = vec![(18.0, 0.001)];
+        let candidates = [1.0, 10.0, 100.0, 1000.0, 5000.0];
+        let mu = optimal_dirichlet_mu(&doc, 20.0, &candidates);
+        assert!(candidates.contains(&mu));
+        assert!(mu <= 10.0);
+    }
+
+    #[test]
+    fn optimal_dirichlet_mu_over_collection_sums_across_documents() {
+        let doc_a: Vec<(f32, f32)> = vec![(3.0, 0.01), (2.0, 0.02)];
+        let doc_b: Vec<(f32, f32)> = vec![(4.0, 0.01), (1.0, 0.02)];
+        let candidates = [1.0, 100.0, 1000.0];
+        let mu = optimal_dirichlet_mu_over_collection(
+            &[(doc_a.as_slice(), 20.0), (doc_b.as_slice(), 15.0)],
+            &candidates,
+        );
+        assert!(candidates.contains(&mu));
+    }
+
+    #[test]
+    fn score_postings_matches_per_posting_bm25() {
+        let tfs = [3.0, 1.0, 0.0, 5.0];
+        let doc_lens = [12.0, 8.0, 10.0, 30.0];
+        let avg_doc_len = 10.0;
+        let k1 = 1.2;
+        let b = 0.75;
+        let idf = bm25_idf_plus1(1000, 10);
+
+        let mut out = [0.0; 4];
+        score_postings(&tfs, &doc_lens, avg_doc_len, k1, b, idf, &mut out);
+
+        for i in 0..tfs.len() {
+            let expected = idf * bm25_tf(tfs[i], doc_lens[i], avg_doc_len, k1, b);
+            assert!((out[i] - expected).abs() < 1e-5, "posting {i}: {} vs {}", out[i], expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn score_postings_panics_on_mismatched_lengths() {
+        let tfs = [1.0, 2.0];
+        let doc_lens = [10.0];
+        let mut out = [0.0; 2];
+        score_postings(&tfs, &doc_lens, 10.0, 1.2, 0.75, 1.0, &mut out);
+    }
+
+    #[test]
+    fn score_postings_lm_matches_per_posting_dirichlet() {
+        let tfs = [3.0, 0.0, 7.0];
+        let doc_lens = [12.0, 8.0, 40.0];
+        let p_corpus = [0.01, 0.02, 0.005];
+        let mu = 1000.0;
+
+        let mut out = [0.0; 3];
+        score_postings_lm(&tfs, &doc_lens, &p_corpus, mu, &mut out);
+
+        for i in 0..tfs.len() {
+            let expected = lm_smoothed_p(tfs[i], doc_lens[i], p_corpus[i], SmoothingMethod::Dirichlet { mu });
+            assert!((out[i] - expected).abs() < 1e-5, "posting {i}: {} vs {}", out[i], expected);
+        }
+    }
+
+    #[test]
+    fn score_postings_lm_matches_scalar_zero_denominator_case() {
+        // doc_len == 0.0 and mu == 0.0 make the Dirichlet denominator 0.0; the scalar path
+        // returns 0.0 rather than dividing through a clamped denominator.
+        let tfs = [5.0];
+        let doc_lens = [0.0];
+        let p_corpus = [0.1];
+        let mu = 0.0;
+
+        let mut out = [0.0];
+        score_postings_lm(&tfs, &doc_lens, &p_corpus, mu, &mut out);
+
+        let expected = lm_smoothed_p(tfs[0], doc_lens[0], p_corpus[0], SmoothingMethod::Dirichlet { mu });
+        assert_eq!(expected, 0.0);
+        assert_eq!(out[0], expected);
+    }
 }